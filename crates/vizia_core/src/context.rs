@@ -0,0 +1,396 @@
+use std::{any::Any, cell::RefCell, collections::{HashMap, HashSet, VecDeque}, rc::Rc};
+
+use glutin::{event::ModifiersState, window::WindowId};
+
+use crate::{CachedData, Data, DragData, DropData, Entity, Enviroment, Event, IdManager, MouseButton, MouseState, Propagation, Style, Tree, TreeExt, WindowEvent, layout::cache::BoundingBox};
+
+/// A node in the view tree. Views render themselves into the entity they
+/// were built on and react to events routed to it.
+pub trait View {
+    fn body(&mut self, cx: &mut Context);
+
+    /// Short debug label for an entity built by this view. Defaults to the
+    /// entity itself.
+    fn debug(&self, entity: Entity) -> String {
+        format!("{}", entity)
+    }
+}
+
+/// Central application state: the entity tree, per-entity styling/layout
+/// caches, the live view and model instances, and everything the run loop
+/// needs to turn OS events into application state changes.
+pub struct Context {
+    pub entity_manager: IdManager,
+    pub tree: Tree,
+    pub current: Entity,
+    pub count: usize,
+    pub views: HashMap<Entity, Box<dyn View>>,
+    pub state: HashMap<Entity, Box<dyn Any>>,
+    pub data: Data,
+    pub style: Rc<RefCell<Style>>,
+    pub cache: CachedData,
+    pub enviroment: Enviroment,
+    pub event_queue: VecDeque<Event>,
+    /// Window id -> last known cursor position in that window, kept
+    /// per-window so moving the mouse in one window can't resolve hover (or
+    /// anything else keyed on cursor position) against another window.
+    pub mouse: HashMap<WindowId, MouseState>,
+    /// Window id -> currently hovered entity in that window, kept per-window
+    /// so hover never leaks across windows that share the same tree.
+    pub hovered: HashMap<WindowId, Entity>,
+    /// Window id -> currently focused entity in that window, kept per-window
+    /// so Tab traversal (and KeyDown/KeyUp delivery) in one window can never
+    /// move focus into an entity that lives in a different OS window.
+    pub focused: HashMap<WindowId, Entity>,
+    pub state_count: usize,
+    /// Drag-and-drop state: the in-progress payload, the entity the primary
+    /// button was pressed on (a drag starts from it only if it opted in via
+    /// `make_drag_source`), the press origin used for the movement threshold, and the
+    /// drop target currently under the cursor. Keyed by window id, like
+    /// `mouse`/`hovered`/`focused` above, so a press or drag started in one
+    /// window can never be read against another window's cursor or hover
+    /// data.
+    pub dragging: HashMap<WindowId, DragData>,
+    pub drag_source: HashMap<WindowId, Entity>,
+    pub press_origin: HashMap<WindowId, (f32, f32)>,
+    pub drag_target: HashMap<WindowId, Entity>,
+    /// Entities that opted in (via `make_drag_source`/`make_drop_target`) to
+    /// participate in drag-and-drop, rather than every entity being draggable.
+    pub drag_sources: HashSet<Entity>,
+    pub drop_targets: HashSet<Entity>,
+    /// Per-entity post-layout bounds, recomputed by `after_layout` each frame.
+    pub hitboxes: Vec<Hitbox>,
+    /// Window id -> logical root entity for that window.
+    pub windows: HashMap<WindowId, Entity>,
+    pub window_actions: VecDeque<WindowAction>,
+    /// Per-entity closures registered via `on_press`/`on_release`/`on_hover`/
+    /// `on_mouse_down`.
+    pub handlers: HashMap<Entity, HashMap<HandlerKind, Box<dyn FnMut(&mut Context)>>>,
+    /// Entities that opted in to keyboard focus traversal.
+    pub focusable: HashSet<Entity>,
+    pub modifiers: ModifiersState,
+}
+
+/// A view's final bounds for one frame, registered during the `after_layout`
+/// hitbox pass so that hover can be resolved against current geometry rather
+/// than the rects painted on the previous frame.
+#[derive(Debug, Clone, Copy)]
+pub struct Hitbox {
+    pub entity: Entity,
+    pub bounds: BoundingBox,
+    pub z_index: i32,
+    /// Root entity of the window this hitbox belongs to, so hover resolution
+    /// never crosses from one window's geometry into another's.
+    pub window_root: Entity,
+}
+
+impl Hitbox {
+    fn contains_point(&self, posx: f32, posy: f32) -> bool {
+        posx >= self.bounds.x
+            && posx <= self.bounds.x + self.bounds.w
+            && posy >= self.bounds.y
+            && posy <= self.bounds.y + self.bounds.h
+    }
+}
+
+fn is_descendant_of(tree: &Tree, entity: Entity, ancestor: Entity) -> bool {
+    let mut current = Some(entity);
+    while let Some(e) = current {
+        if e == ancestor {
+            return true;
+        }
+        current = e.parent(tree);
+    }
+
+    false
+}
+
+impl Context {
+    /// Walks the tree in paint order and records each view's post-layout
+    /// bounds as a hitbox, tagged with the root of whichever window it
+    /// belongs to. Run once per relayout so hover is always resolved
+    /// against the current frame's geometry rather than a stale flat list
+    /// of rects.
+    pub fn after_layout(&mut self, window_roots: &[Entity]) {
+        self.hitboxes.clear();
+
+        for (z_index, entity) in self.tree.clone().into_iter().enumerate() {
+            let window_root = match window_roots
+                .iter()
+                .find(|&&root| entity == root || is_descendant_of(&self.tree, entity, root))
+            {
+                Some(&root) => root,
+                None => continue,
+            };
+
+            self.hitboxes.push(Hitbox {
+                entity,
+                bounds: self.cache.get_bounds(entity),
+                z_index: z_index as i32,
+                window_root,
+            });
+        }
+    }
+
+    /// The entity considered hovered in `window_id`: its tracked hover
+    /// target, or that window's own root if hover hasn't been resolved there
+    /// yet (e.g. no `CursorMoved`/relayout has happened in it since it
+    /// opened). Falls back to `Entity::root()` only if `window_id` isn't a
+    /// known window at all, so routing never mistakenly lands on the main
+    /// window's root instead.
+    pub fn hovered_or_root(&self, window_id: WindowId) -> Entity {
+        self.hovered
+            .get(&window_id)
+            .copied()
+            .unwrap_or_else(|| self.windows.get(&window_id).copied().unwrap_or(Entity::root()))
+    }
+
+    /// Resolves hover for a single window from the current hitbox list: the
+    /// topmost hitbox (highest z-index, i.e. last painted) belonging to
+    /// `window_root` and containing the cursor wins. Diffs against that
+    /// window's previously hovered entity and emits `MouseOut`/`MouseLeave`
+    /// to the old entity and `MouseOver`/`MouseEnter` to the new one.
+    /// Hitboxes belonging to other windows are never considered, so a click
+    /// or cursor move in one window can never resolve against another
+    /// window's geometry.
+    pub fn resolve_hover(&mut self, window_id: WindowId, window_root: Entity) {
+        let mouse = self.mouse.get(&window_id).cloned().unwrap_or_default();
+        let cursorx = mouse.cursorx;
+        let cursory = mouse.cursory;
+
+        let hovered = self
+            .hitboxes
+            .iter()
+            .filter(|hitbox| hitbox.window_root == window_root && hitbox.contains_point(cursorx, cursory))
+            .max_by_key(|hitbox| hitbox.z_index)
+            .map(|hitbox| hitbox.entity)
+            .unwrap_or(window_root);
+
+        let old_hovered = self.hovered.get(&window_id).copied().unwrap_or(window_root);
+
+        if hovered != old_hovered {
+            self.event_queue.push_back(
+                Event::new(WindowEvent::MouseOut).target(old_hovered).propagate(Propagation::Direct),
+            );
+            self.event_queue.push_back(
+                Event::new(WindowEvent::MouseLeave).target(old_hovered).propagate(Propagation::Direct),
+            );
+
+            self.hovered.insert(window_id, hovered);
+
+            self.event_queue.push_back(
+                Event::new(WindowEvent::MouseOver).target(hovered).propagate(Propagation::Direct),
+            );
+            self.event_queue.push_back(
+                Event::new(WindowEvent::MouseEnter).target(hovered).propagate(Propagation::Direct),
+            );
+
+            self.invoke_handler(hovered, HandlerKind::Hover);
+        }
+    }
+
+    /// Marks the entity currently being built as eligible to receive
+    /// keyboard focus via Tab/Shift-Tab traversal or programmatic `focus`
+    /// calls. Non-interactive views (e.g. `Label`, `Element`) should never
+    /// call this; interactive ones (`Textbox`, `Button`, `Checkbox`) opt in
+    /// at construction.
+    pub fn make_focusable(&mut self) {
+        self.focusable.insert(self.current);
+    }
+
+    /// The current focus order for one window: focusable entities belonging
+    /// to `window_root`, in tree (paint) order.
+    fn focus_order(&self, window_root: Entity) -> Vec<Entity> {
+        self.tree
+            .clone()
+            .into_iter()
+            .filter(|entity| {
+                self.focusable.contains(entity)
+                    && (*entity == window_root || is_descendant_of(&self.tree, *entity, window_root))
+            })
+            .collect()
+    }
+
+    /// Moves keyboard focus to `entity` within `window_id`, emitting
+    /// `FocusOut` to the window's previously focused entity and `FocusIn` to
+    /// the new one.
+    pub fn focus(&mut self, window_id: WindowId, entity: Entity) {
+        let old_focused = self.focused.get(&window_id).copied();
+
+        if old_focused == Some(entity) {
+            return;
+        }
+
+        if let Some(old_focused) = old_focused {
+            self.event_queue.push_back(
+                Event::new(WindowEvent::FocusOut).target(old_focused).propagate(Propagation::Direct),
+            );
+        }
+
+        self.focused.insert(window_id, entity);
+
+        self.event_queue.push_back(
+            Event::new(WindowEvent::FocusIn).target(entity).propagate(Propagation::Direct),
+        );
+    }
+
+    /// Advances focus to the next focusable entity in `window_id`'s tab
+    /// order, wrapping around to the first once the end is reached.
+    pub fn focus_next(&mut self, window_id: WindowId, window_root: Entity) {
+        let order = self.focus_order(window_root);
+        if order.is_empty() {
+            return;
+        }
+
+        let current = self.focused.get(&window_id).copied();
+        let next = match current.and_then(|entity| order.iter().position(|&e| e == entity)) {
+            Some(index) => order[(index + 1) % order.len()],
+            None => order[0],
+        };
+
+        self.focus(window_id, next);
+    }
+
+    /// Moves focus to the previous focusable entity in `window_id`'s tab
+    /// order, wrapping around to the last once the start is reached.
+    pub fn focus_prev(&mut self, window_id: WindowId, window_root: Entity) {
+        let order = self.focus_order(window_root);
+        if order.is_empty() {
+            return;
+        }
+
+        let current = self.focused.get(&window_id).copied();
+        let prev = match current.and_then(|entity| order.iter().position(|&e| e == entity)) {
+            Some(index) => order[(index + order.len() - 1) % order.len()],
+            None => order[0],
+        };
+
+        self.focus(window_id, prev);
+    }
+
+    /// Moves focus in `window_id` to the nearest focusable ancestor of the
+    /// currently focused entity, if any.
+    pub fn focus_parent(&mut self, window_id: WindowId) {
+        let mut current = self.focused.get(&window_id).and_then(|&entity| entity.parent(&self.tree));
+
+        while let Some(entity) = current {
+            if self.focusable.contains(&entity) {
+                self.focus(window_id, entity);
+                return;
+            }
+
+            current = entity.parent(&self.tree);
+        }
+    }
+
+    /// Marks the entity currently being built as a drag source: pressing
+    /// the primary mouse button on it and moving past the drag threshold
+    /// starts a drag carrying its identity. Entities that don't opt in are
+    /// never draggable, no matter how long the button is held.
+    pub fn make_drag_source(&mut self) {
+        self.drag_sources.insert(self.current);
+    }
+
+    /// Marks the entity currently being built as a drop target: an
+    /// in-progress drag released over it is delivered as a `Drop` event.
+    /// Releasing over an entity that hasn't opted in cancels the drag.
+    pub fn make_drop_target(&mut self) {
+        self.drop_targets.insert(self.current);
+    }
+}
+
+/// Which per-entity interaction a registered handler closure fires on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HandlerKind {
+    Press,
+    Release,
+    Hover,
+    MouseDown(MouseButton),
+}
+
+impl Context {
+    /// Registers a closure on the entity currently being built, run when
+    /// the primary mouse button is pressed and released over it. Registering
+    /// a press handler implies the entity is an interactive control, so it's
+    /// also made focusable, as if `make_focusable` had been called.
+    pub fn on_press<F: 'static + FnMut(&mut Context)>(&mut self, callback: F) -> &mut Self {
+        self.handlers.entry(self.current).or_default().insert(HandlerKind::Press, Box::new(callback));
+        self.make_focusable();
+
+        self
+    }
+
+    /// Registers a closure on the entity currently being built, run on every
+    /// primary mouse-button release while hovering it, regardless of where
+    /// the press originated (e.g. a drag that ends over it). Implies the
+    /// entity is focusable, as if `make_focusable` had been called.
+    pub fn on_release<F: 'static + FnMut(&mut Context)>(&mut self, callback: F) -> &mut Self {
+        self.handlers.entry(self.current).or_default().insert(HandlerKind::Release, Box::new(callback));
+        self.make_focusable();
+
+        self
+    }
+
+    /// Registers a closure on the entity currently being built, run when
+    /// the cursor enters it. Hover isn't a keyboard interaction, so unlike
+    /// the other handler kinds this doesn't imply focusability.
+    pub fn on_hover<F: 'static + FnMut(&mut Context)>(&mut self, callback: F) -> &mut Self {
+        self.handlers.entry(self.current).or_default().insert(HandlerKind::Hover, Box::new(callback));
+
+        self
+    }
+
+    /// Registers a closure on the entity currently being built, run when
+    /// `button` is pressed down over it. Implies the entity is focusable, as
+    /// if `make_focusable` had been called.
+    pub fn on_mouse_down<F: 'static + FnMut(&mut Context)>(&mut self, button: MouseButton, callback: F) -> &mut Self {
+        self.handlers.entry(self.current).or_default().insert(HandlerKind::MouseDown(button), Box::new(callback));
+        self.make_focusable();
+
+        self
+    }
+
+    /// Runs the handler registered for `kind` on `entity`, if any. Follows a
+    /// remove-call-reinsert pattern, since the handler needs `&mut Context`
+    /// while itself living inside `self.handlers`.
+    pub fn invoke_handler(&mut self, entity: Entity, kind: HandlerKind) {
+        if let Some(mut handler) = self.handlers.get_mut(&entity).and_then(|handlers| handlers.remove(&kind)) {
+            handler(self);
+            self.handlers.entry(entity).or_default().insert(kind, handler);
+        }
+    }
+}
+
+/// A pending request to open a new window or rebuild an existing one,
+/// queued on [`Context`] and drained once per `MainEventsCleared` by the
+/// run loop, which is the only place that has access to the event loop's
+/// window target needed to actually create a `glutin` window.
+pub enum WindowAction {
+    Open(Entity, Rc<dyn Fn(&mut Context)>),
+    ReplaceRoot(WindowId, Rc<dyn Fn(&mut Context)>),
+}
+
+impl Context {
+    /// Opens an additional window at runtime, built by `builder` into its
+    /// own root entity. The window appears once the current event loop
+    /// iteration finishes.
+    pub fn open_window<F: 'static + Fn(&mut Context)>(&mut self, builder: F) {
+        let root = self.entity_manager.create();
+        // No parent: each window's root is its own top-level root, not a
+        // child of the main window's root, so ancestry walks (and therefore
+        // render/hitbox/focus filtering) never cross from one window's tree
+        // into another's.
+        self.tree.add(root, None).expect("Failed to add window root");
+        self.cache.add(root);
+
+        self.window_actions.push_back(WindowAction::Open(root, Rc::new(builder)));
+    }
+
+    /// Swaps the builder for an existing window and rebuilds its subtree in
+    /// place, tearing down the entities and views that made up the old
+    /// root first. Used for whole-screen navigation without restarting the
+    /// app.
+    pub fn replace_root_view<F: 'static + Fn(&mut Context)>(&mut self, window_id: WindowId, builder: F) {
+        self.window_actions.push_back(WindowAction::ReplaceRoot(window_id, Rc::new(builder)));
+    }
+}
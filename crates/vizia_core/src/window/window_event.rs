@@ -9,6 +9,7 @@ use vizia_window::{Position, WindowSize};
 pub enum DropData {
     File(PathBuf),
     Id(Entity),
+    Text(String),
 }
 
 impl From<Entity> for DropData {
@@ -23,6 +24,50 @@ impl From<PathBuf> for DropData {
     }
 }
 
+impl From<String> for DropData {
+    fn from(value: String) -> Self {
+        DropData::Text(value)
+    }
+}
+
+/// The payload carried by an in-progress drag, started by a drag source and
+/// offered to drop targets as the cursor moves. Converted into a [`DropData`]
+/// when it is released over a drop target.
+#[derive(Debug, Clone)]
+pub enum DragData {
+    File(PathBuf),
+    Id(Entity),
+    Text(String),
+}
+
+impl From<Entity> for DragData {
+    fn from(value: Entity) -> Self {
+        DragData::Id(value)
+    }
+}
+
+impl From<PathBuf> for DragData {
+    fn from(value: PathBuf) -> Self {
+        DragData::File(value)
+    }
+}
+
+impl From<String> for DragData {
+    fn from(value: String) -> Self {
+        DragData::Text(value)
+    }
+}
+
+impl From<DragData> for DropData {
+    fn from(value: DragData) -> Self {
+        match value {
+            DragData::File(path) => DropData::File(path),
+            DragData::Id(entity) => DropData::Id(entity),
+            DragData::Text(text) => DropData::Text(text),
+        }
+    }
+}
+
 /// Events generated by the application in response to OS events as well as events that can be used
 /// to set properties of the window.
 #[derive(Debug, Clone)]
@@ -31,6 +76,12 @@ pub enum WindowEvent {
     WindowClose,
     /// Emitted when a file is dragged and then dropped onto the window.
     Drop(DropData),
+    /// Emitted to the view under the cursor when an in-progress drag moves
+    /// onto it, whether or not that view is a drop target.
+    DragEnter,
+    /// Emitted to the previous view under the cursor when an in-progress drag
+    /// moves off it, whether or not that view is a drop target.
+    DragLeave,
     /// Emitted when a mouse button is double clicked.
     MouseDoubleClick(MouseButton),
     /// Emitted when a mouse button is triple clicked
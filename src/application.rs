@@ -1,13 +1,64 @@
-use std::{cell::RefCell, collections::{HashMap, VecDeque}, rc::Rc};
+use std::{cell::RefCell, collections::{HashMap, HashSet, VecDeque}, rc::Rc};
 
 use femtovg::{Align, Baseline, Canvas, Paint, Path, renderer::OpenGl};
-use glutin::{ContextBuilder, event::VirtualKeyCode, event_loop::{ControlFlow, EventLoop}, window::WindowBuilder};
+use glutin::{ContextBuilder, ContextWrapper, PossiblyCurrent, event::{ModifiersState, VirtualKeyCode}, event_loop::{ControlFlow, EventLoop}, window::{Window, WindowBuilder, WindowId}};
 use morphorm::Units;
 
-use crate::{CachedData, Color, Context, Data, Entity, Enviroment, Event, EventManager, IdManager, MouseButton, MouseButtonState, MouseState, Propagation, Style, Tree, TreeExt, WindowEvent, apply_hover, scan_to_code, style, vcode_to_code, vk_to_key};
+use crate::{CachedData, Color, Context, Data, DragData, DropData, Entity, Enviroment, Event, EventManager, HandlerKind, IdManager, MouseButton, MouseButtonState, MouseState, Propagation, Style, Tree, TreeExt, WindowAction, WindowEvent, scan_to_code, style, vcode_to_code, vk_to_key};
+
+/// Cursor must move this many pixels away from the press origin before a
+/// press-and-hold on a drag source turns into a drag.
+const DRAG_THRESHOLD: f32 = 4.0;
 
 static FONT: &[u8] = include_bytes!("Roboto-Regular.ttf");
 
+/// Per-window `glutin`/`femtovg` render state. Kept out of [`Context`] (which
+/// only tracks the logical root entity per window) since a GL context and
+/// canvas aren't meaningful outside the render loop that owns them.
+struct WindowRenderState {
+    handle: ContextWrapper<PossiblyCurrent, Window>,
+    canvas: Canvas<OpenGl>,
+    font: femtovg::FontId,
+    size: glutin::dpi::PhysicalSize<u32>,
+    root: Entity,
+}
+
+fn is_descendant_of(tree: &Tree, entity: Entity, ancestor: Entity) -> bool {
+    let mut current = Some(entity);
+    while let Some(e) = current {
+        if e == ancestor {
+            return true;
+        }
+        current = e.parent(tree);
+    }
+
+    false
+}
+
+fn open_window_render_state(
+    root: Entity,
+    window_target: &glutin::event_loop::EventLoopWindowTarget<()>,
+) -> WindowRenderState {
+    let handle = ContextBuilder::new()
+        .build_windowed(WindowBuilder::new(), window_target)
+        .expect("Failed to build windowed context");
+
+    let handle = unsafe { handle.make_current().unwrap() };
+
+    let renderer = OpenGl::new(|s| handle.context().get_proc_address(s) as *const _)
+        .expect("Cannot create renderer");
+    let mut canvas = Canvas::new(renderer).expect("Cannot create canvas");
+
+    let font = canvas.add_font_mem(FONT).expect("Failed to load font");
+
+    let dpi_factor = handle.window().scale_factor();
+    let size = handle.window().inner_size();
+
+    canvas.set_size(size.width as u32, size.height as u32, dpi_factor as f32);
+
+    WindowRenderState { handle, canvas, font, size, root }
+}
+
 pub struct Application {
     context: Context,
     builder: Option<Box<dyn Fn(&mut Context)>>,
@@ -27,21 +78,35 @@ impl Application {
             current: Entity::root(),
             count: 0,
             views: HashMap::new(),
-            state: HashMap::new(),  
+            state: HashMap::new(),
             data: Data::new(),
             style: Rc::new(RefCell::new(Style::default())),
             cache,
             enviroment: Enviroment::new(),
             event_queue: VecDeque::new(),
-            mouse: MouseState::default(),
-            hovered: Entity::root(),
-            focused: Entity::root(),
+            mouse: HashMap::new(),
+            hovered: HashMap::new(),
+            focused: HashMap::new(),
             state_count: 0,
+            dragging: HashMap::new(),
+            drag_source: HashMap::new(),
+            press_origin: HashMap::new(),
+            drag_target: HashMap::new(),
+            drag_sources: HashSet::new(),
+            drop_targets: HashSet::new(),
+            hitboxes: Vec::new(),
+            windows: HashMap::new(),
+            window_actions: VecDeque::new(),
+            handlers: HashMap::new(),
+            focusable: HashSet::new(),
+            modifiers: ModifiersState::empty(),
         };
 
         context.entity_manager.create();
 
-        
+        context.windows.insert(WindowId::dummy(), Entity::root());
+
+
 
         Self {
             context,
@@ -62,37 +127,41 @@ impl Application {
         self
     }
 
+    /// Queues an additional window to be opened once the event loop starts,
+    /// built by `builder` into its own root entity and canvas.
+    pub fn open_window<F: 'static + Fn(&mut Context)>(mut self, builder: F) -> Self {
+        self.context.open_window(builder);
+
+        self
+    }
+
     pub fn run(mut self) {
 
         let mut context = self.context;
-        
-        let event_loop = EventLoop::new();
-        
-        let handle = ContextBuilder::new()
-            .build_windowed(WindowBuilder::new(), &event_loop)
-            .expect("Failed to build windowed context");
 
-        let handle = unsafe { handle.make_current().unwrap() };
+        let event_loop = EventLoop::new();
 
-        let renderer = OpenGl::new(|s| handle.context().get_proc_address(s) as *const _)
-            .expect("Cannot create renderer");
-        let mut canvas = Canvas::new(renderer).expect("Cannot create canvas");
+        let main_render_state = open_window_render_state(Entity::root(), &event_loop);
+        let main_window_id = main_render_state.handle.window().id();
 
-        let font = canvas.add_font_mem(FONT).expect("Failed to load font");
+        context.windows.remove(&WindowId::dummy());
+        context.windows.insert(main_window_id, Entity::root());
 
-        let dpi_factor = handle.window().scale_factor();
-        let size = handle.window().inner_size();
+        let mut windows = HashMap::new();
+        windows.insert(main_window_id, main_render_state);
 
         let clear_color = context.style.borrow_mut().background_color.get(Entity::root()).cloned().unwrap_or_default();
 
-        canvas.set_size(size.width as u32, size.height as u32, dpi_factor as f32);
-        canvas.clear_rect(
-            0,
-            0,
-            size.width as u32,
-            size.height as u32,
-            clear_color.into(),
-        );
+        {
+            let main_render_state = windows.get_mut(&main_window_id).unwrap();
+            main_render_state.canvas.clear_rect(
+                0,
+                0,
+                main_render_state.size.width as u32,
+                main_render_state.size.height as u32,
+                clear_color.into(),
+            );
+        }
 
         context
             .cache
@@ -114,7 +183,7 @@ impl Application {
 
         let builder = self.builder.take();
 
-        event_loop.run(move |event, _, control_flow|{
+        event_loop.run(move |event, window_target, control_flow|{
             *control_flow = ControlFlow::Wait;
 
             match event {
@@ -129,6 +198,84 @@ impl Application {
                         context.enviroment.needs_rebuild = false;
                     }
 
+                    // Drain queued window actions: open any newly requested windows
+                    // and rebuild any windows whose root view was replaced.
+                    while let Some(action) = context.window_actions.pop_front() {
+                        match action {
+                            WindowAction::Open(root, builder) => {
+                                let render_state = open_window_render_state(root, window_target);
+                                let window_id = render_state.handle.window().id();
+
+                                // Seed the new root's layout size from the real window
+                                // size (which `Resized` then keeps updated), so the first
+                                // layout pass doesn't run against the `CachedData`/`Style`
+                                // defaults.
+                                context.cache.set_width(root, render_state.size.width as f32);
+                                context.cache.set_height(root, render_state.size.height as f32);
+
+                                context.style.borrow_mut().width.insert(root, Units::Pixels(render_state.size.width as f32));
+                                context.style.borrow_mut().height.insert(root, Units::Pixels(render_state.size.height as f32));
+
+                                context.windows.insert(window_id, root);
+                                windows.insert(window_id, render_state);
+
+                                let prev = context.current;
+                                context.current = root;
+                                (builder)(&mut context);
+                                context.current = prev;
+                            }
+
+                            WindowAction::ReplaceRoot(window_id, builder) => {
+                                if let Some(&root) = context.windows.get(&window_id) {
+                                    let stale: Vec<Entity> = context
+                                        .tree
+                                        .clone()
+                                        .into_iter()
+                                        .filter(|&entity| entity != root && is_descendant_of(&context.tree, entity, root))
+                                        .collect();
+
+                                    for &entity in stale.iter() {
+                                        context.views.remove(&entity);
+                                        context.state.remove(&entity);
+                                        context.cache.remove(entity);
+                                        let _ = context.tree.remove(entity);
+
+                                        // The entity manager reuses freed ids, so any
+                                        // registration left behind here would silently
+                                        // reattach to whatever new view lands on the id.
+                                        context.handlers.remove(&entity);
+                                        context.focusable.remove(&entity);
+                                        context.drag_sources.remove(&entity);
+                                        context.drop_targets.remove(&entity);
+                                    }
+
+                                    // Same reuse hazard as above: if the freed id were left
+                                    // as the focused/dragging entity, a new unrelated view
+                                    // that happens to land on it would silently inherit focus
+                                    // or an in-progress drag it never opted into.
+                                    if context.focused.get(&window_id).is_some_and(|e| stale.contains(e)) {
+                                        context.focused.insert(window_id, root);
+                                    }
+
+                                    if context.drag_source.get(&window_id).is_some_and(|e| stale.contains(e)) {
+                                        context.drag_source.remove(&window_id);
+                                        context.press_origin.remove(&window_id);
+                                        context.dragging.remove(&window_id);
+                                    }
+
+                                    if context.drag_target.get(&window_id).is_some_and(|e| stale.contains(e)) {
+                                        context.drag_target.remove(&window_id);
+                                    }
+
+                                    let prev = context.current;
+                                    context.current = root;
+                                    (builder)(&mut context);
+                                    context.current = prev;
+                                }
+                            }
+                        }
+                    }
+
                     // Events
                     while !context.event_queue.is_empty() {
                         event_manager.flush_events(&mut context);
@@ -137,7 +284,7 @@ impl Application {
                     // Updates
                     for entity in context.tree.clone().into_iter() {
                         let mut observers = Vec::new();
-                     
+
                         if let Some(model_list) = context.data.model_data.get(entity) {
                             for model in model_list.iter() {
                                 //observers = model.update();
@@ -156,8 +303,8 @@ impl Application {
                                 view.body(&mut context);
                                 context.current = prev;
                                 context.count = prev_count;
-                    
-                
+
+
                                 context.views.insert(*observer, view);
                             }
                         }
@@ -167,7 +314,7 @@ impl Application {
                                 model.reset();
                             }
                         }
-                        
+
                     }
 
                     // Styling (TODO)
@@ -175,49 +322,106 @@ impl Application {
                     // Layout
                     morphorm::layout(&mut context.cache, &context.tree, &context.style.borrow());
 
-                    handle.window().request_redraw();
+                    // Hitbox pass: register each view's post-layout bounds, tagged by
+                    // window, and re-resolve hover per window against the current
+                    // frame's geometry.
+                    let window_roots: Vec<Entity> = windows.values().map(|render_state| render_state.root).collect();
+                    context.after_layout(&window_roots);
+
+                    for (&window_id, render_state) in windows.iter() {
+                        context.resolve_hover(window_id, render_state.root);
+                    }
+
+                    for render_state in windows.values() {
+                        render_state.handle.window().request_redraw();
+                    }
                 }
 
-                glutin::event::Event::RedrawRequested(_) => {
-                    // Redraw here
-                    //println!("Redraw");
-                    let clear_color = context.style.borrow_mut().background_color.get(Entity::root()).cloned().unwrap_or(Color::white());
-                    canvas.clear_rect(
-                        0,
-                        0,
-                        size.width as u32,
-                        size.height as u32,
-                        clear_color.into(),
-                    );
-                    for entity in context.tree.clone().into_iter() {
-                        //println!("{}", debug(&mut context, entity));
-                        let bounds = context.cache.get_bounds(entity);
-                        let mut path = Path::new();
-                        path.rect(bounds.x, bounds.y, bounds.w, bounds.h);
-
-                        let background_color: femtovg::Color = context.style.borrow_mut().background_color.get(entity).cloned().unwrap_or_default().into();
-                        canvas.fill_path(&mut path, Paint::color(background_color));
-                        
-                        if let Some(text) = context.style.borrow().text.get(entity) {
-                            let mut paint = Paint::color(femtovg::Color::black());
-                            paint.set_font(&[font]);
-                            paint.set_text_align(Align::Center);
-                            paint.set_text_baseline(Baseline::Middle);
-                            canvas.fill_text(bounds.x + bounds.w / 2.0, bounds.y + bounds.h / 2.0, text, paint);
+                glutin::event::Event::RedrawRequested(window_id) => {
+                    if let Some(render_state) = windows.get_mut(&window_id) {
+                        let root = render_state.root;
+                        let size = render_state.size;
+                        let font = render_state.font;
+                        let canvas = &mut render_state.canvas;
+
+                        let clear_color = context.style.borrow_mut().background_color.get(root).cloned().unwrap_or(Color::white());
+                        canvas.clear_rect(
+                            0,
+                            0,
+                            size.width as u32,
+                            size.height as u32,
+                            clear_color.into(),
+                        );
+                        for entity in context.tree.clone().into_iter().filter(|&entity| is_descendant_of(&context.tree, entity, root)) {
+                            //println!("{}", debug(&mut context, entity));
+                            let bounds = context.cache.get_bounds(entity);
+                            let mut path = Path::new();
+                            path.rect(bounds.x, bounds.y, bounds.w, bounds.h);
+
+                            let background_color: femtovg::Color = context.style.borrow_mut().background_color.get(entity).cloned().unwrap_or_default().into();
+                            canvas.fill_path(&mut path, Paint::color(background_color));
+
+                            if let Some(text) = context.style.borrow().text.get(entity) {
+                                let mut paint = Paint::color(femtovg::Color::black());
+                                paint.set_font(&[font]);
+                                paint.set_text_align(Align::Center);
+                                paint.set_text_baseline(Baseline::Middle);
+                                canvas.fill_text(bounds.x + bounds.w / 2.0, bounds.y + bounds.h / 2.0, text, paint);
+                            }
                         }
-                    }
 
-                    canvas.flush();
-                    handle.swap_buffers().expect("Failed to swap buffers");
+                        canvas.flush();
+                        render_state.handle.swap_buffers().expect("Failed to swap buffers");
+                    }
                 }
 
                 glutin::event::Event::WindowEvent {
-                    window_id: _,
+                    window_id,
                     event,
                 } => {
                     match event {
+                        glutin::event::WindowEvent::Resized(physical_size) => {
+                            if let Some(render_state) = windows.get_mut(&window_id) {
+                                let root = render_state.root;
+                                let dpi_factor = render_state.handle.window().scale_factor();
+
+                                render_state.handle.resize(physical_size);
+                                render_state.canvas.set_size(physical_size.width, physical_size.height, dpi_factor as f32);
+                                render_state.size = physical_size;
+
+                                context.cache.set_width(root, physical_size.width as f32);
+                                context.cache.set_height(root, physical_size.height as f32);
+
+                                context.style.borrow_mut().width.insert(root, Units::Pixels(physical_size.width as f32));
+                                context.style.borrow_mut().height.insert(root, Units::Pixels(physical_size.height as f32));
+
+                                context.event_queue.push_back(
+                                    Event::new(WindowEvent::Relayout).target(root).propagate(Propagation::Direct),
+                                );
+                            }
+                        }
+
+                        glutin::event::WindowEvent::ModifiersChanged(state) => {
+                            // Tracked independently of the `modifiers` payload on mouse
+                            // events so that held modifiers (e.g. Shift for Shift-Tab)
+                            // are current even if the mouse hasn't moved this frame.
+                            context.modifiers = state;
+                        }
+
                         glutin::event::WindowEvent::CloseRequested => {
-                            *control_flow = ControlFlow::Exit;
+                            windows.remove(&window_id);
+                            context.windows.remove(&window_id);
+                            context.hovered.remove(&window_id);
+                            context.mouse.remove(&window_id);
+                            context.focused.remove(&window_id);
+                            context.dragging.remove(&window_id);
+                            context.drag_source.remove(&window_id);
+                            context.press_origin.remove(&window_id);
+                            context.drag_target.remove(&window_id);
+
+                            if windows.is_empty() {
+                                *control_flow = ControlFlow::Exit;
+                            }
                         }
 
                         glutin::event::WindowEvent::CursorMoved {
@@ -226,10 +430,56 @@ impl Application {
                             modifiers
                         } => {
 
-                            context.mouse.cursorx = position.x as f32;
-                            context.mouse.cursory = position.y as f32;
+                            let mouse = context.mouse.entry(window_id).or_default();
+                            mouse.cursorx = position.x as f32;
+                            mouse.cursory = position.y as f32;
+                            context.modifiers = modifiers;
+
+                            if let Some(&root) = context.windows.get(&window_id) {
+                                context.resolve_hover(window_id, root);
+                            }
 
-                            apply_hover(&mut context);
+                            // If a button is held over a drag source, begin a drag once the
+                            // cursor has moved far enough from the press origin, then keep
+                            // re-targeting the drop target under the cursor for the duration
+                            // of the drag.
+                            if let (Some(&source), Some(&(origin_x, origin_y))) =
+                                (context.drag_source.get(&window_id), context.press_origin.get(&window_id))
+                            {
+                                let mouse = context.mouse.get(&window_id).cloned().unwrap_or_default();
+                                let dx = mouse.cursorx - origin_x;
+                                let dy = mouse.cursory - origin_y;
+
+                                // Only entities that opted in via `make_drag_source` can
+                                // start a drag; everything else is just a normal press.
+                                if !context.dragging.contains_key(&window_id)
+                                    && context.drag_sources.contains(&source)
+                                    && (dx * dx + dy * dy).sqrt() > DRAG_THRESHOLD
+                                {
+                                    context.dragging.insert(window_id, DragData::from(source));
+                                }
+
+                                if context.dragging.contains_key(&window_id) {
+                                    let target = context.hovered_or_root(window_id);
+                                    if context.drag_target.get(&window_id) != Some(&target) {
+                                        if let Some(old_target) = context.drag_target.get(&window_id) {
+                                            context.event_queue.push_back(
+                                                Event::new(WindowEvent::DragLeave)
+                                                    .target(*old_target)
+                                                    .propagate(Propagation::Direct),
+                                            );
+                                        }
+
+                                        context.event_queue.push_back(
+                                            Event::new(WindowEvent::DragEnter)
+                                                .target(target)
+                                                .propagate(Propagation::Direct),
+                                        );
+
+                                        context.drag_target.insert(window_id, target);
+                                    }
+                                }
+                            }
                         }
 
                         glutin::event::WindowEvent::MouseInput {
@@ -250,13 +500,83 @@ impl Application {
                                 glutin::event::ElementState::Released => MouseButtonState::Released,
                             };
 
+                            context.modifiers = modifiers;
+
+                            let hovered = context.hovered_or_root(window_id);
+
                             match state {
                                 MouseButtonState::Pressed => {
-                                    context.event_queue.push_back(Event::new(WindowEvent::MouseDown(button)).target(context.hovered).propagate(Propagation::Up));
+                                    context.event_queue.push_back(Event::new(WindowEvent::MouseDown(button)).target(hovered).propagate(Propagation::Up));
+                                    context.invoke_handler(hovered, HandlerKind::MouseDown(button));
+
+                                    if button == MouseButton::Left {
+                                        // Mirrors how resolve_hover queues the real MouseOver
+                                        // event and fires its handler at the same site: push
+                                        // the real PressDown event so it's actually delivered
+                                        // through propagation, not just a handler side-channel.
+                                        context.event_queue.push_back(
+                                            Event::new(WindowEvent::PressDown { mouse: true }).target(hovered).propagate(Propagation::Up),
+                                        );
+
+                                        let mouse = context.mouse.get(&window_id).cloned().unwrap_or_default();
+                                        context.press_origin.insert(window_id, (mouse.cursorx, mouse.cursory));
+
+                                        // Remember the pressed entity so a release over it can be
+                                        // recognised as a click; whether it may also start a drag
+                                        // is decided in `CursorMoved`.
+                                        context.drag_source.insert(window_id, hovered);
+                                    }
                                 }
 
                                 MouseButtonState::Released => {
-                                    context.event_queue.push_back(Event::new(WindowEvent::MouseUp(button)).target(context.hovered).propagate(Propagation::Up));
+                                    context.event_queue.push_back(Event::new(WindowEvent::MouseUp(button)).target(hovered).propagate(Propagation::Up));
+
+                                    // A drag is only ever started by the primary button (see
+                                    // `MouseButtonState::Pressed` above), so only its own
+                                    // release may finalize or cancel one; releasing some
+                                    // other button mid-drag (e.g. a right-click) must leave
+                                    // the in-progress drag untouched.
+                                    if button == MouseButton::Left {
+                                        if let Some(drag_data) = context.dragging.remove(&window_id) {
+                                            // Only deliver the drop if the release target opted in via
+                                            // `make_drop_target` (an opted-in target receives every
+                                            // payload kind); otherwise the drag is simply cancelled.
+                                            if let Some(&target) = context.drag_target.get(&window_id) {
+                                                if context.drop_targets.contains(&target) {
+                                                    context.event_queue.push_back(
+                                                        Event::new(WindowEvent::Drop(DropData::from(drag_data)))
+                                                            .target(target)
+                                                            .propagate(Propagation::Direct),
+                                                    );
+                                                }
+                                            }
+                                        } else if context.drag_source.get(&window_id) == Some(&hovered) {
+                                            // Primary button released over the same view it was
+                                            // pressed on, with no drag in between: a completed click.
+                                            // Press only fires for this narrower case.
+                                            context.event_queue.push_back(
+                                                Event::new(WindowEvent::Press { mouse: true }).target(hovered).propagate(Propagation::Up),
+                                            );
+                                            context.invoke_handler(hovered, HandlerKind::Press);
+                                        }
+                                    }
+
+                                    // Release is broader than Press: it fires on every primary
+                                    // mouse-up while hovering the entity, regardless of where
+                                    // the press originated (e.g. a drag that ends over it), so
+                                    // it isn't just a duplicate of the Press call above.
+                                    if button == MouseButton::Left {
+                                        context.invoke_handler(hovered, HandlerKind::Release);
+
+                                        // Only the primary button's own release ends its
+                                        // press/drag bookkeeping; releasing some other
+                                        // button (e.g. a right-click) while the left button
+                                        // is still held must not wipe a pending left-button
+                                        // press or in-progress drag.
+                                        context.press_origin.remove(&window_id);
+                                        context.drag_source.remove(&window_id);
+                                        context.drag_target.remove(&window_id);
+                                    }
                                 }
                             }
                         }
@@ -278,45 +598,46 @@ impl Application {
                                 glutin::event::ElementState::Released => MouseButtonState::Released,
                             };
 
-	                        // Prefer virtual keycodes to scancodes, as scancodes aren't uniform between platforms
-	                        let code = if let Some(vkey) = input.virtual_keycode {
-		                        vcode_to_code(vkey)
-	                        } else {
-		                        scan_to_code(input.scancode)
-	                        };
-
-                            let key = vk_to_key(
-                                input.virtual_keycode.unwrap_or(VirtualKeyCode::NoConvert),
-                            );
-
-                            match s {
-                                MouseButtonState::Pressed => {
-                                    if context.focused != Entity::null() {
-                                        context.event_queue.push_back(
-                                            Event::new(WindowEvent::KeyDown(code, key))
-                                                .target(context.focused)
-                                                .propagate(Propagation::DownUp),
-                                        );
-                                    } else {
+                            // Tab/Shift-Tab move keyboard focus along the focus order instead
+                            // of being delivered to the focused view like an ordinary key.
+                            if input.virtual_keycode == Some(VirtualKeyCode::Tab) {
+                                if s == MouseButtonState::Pressed {
+                                    if let Some(&root) = context.windows.get(&window_id) {
+                                        if context.modifiers.shift() {
+                                            context.focus_prev(window_id, root);
+                                        } else {
+                                            context.focus_next(window_id, root);
+                                        }
+                                    }
+                                }
+                            } else {
+	                            // Prefer virtual keycodes to scancodes, as scancodes aren't uniform between platforms
+	                            let code = if let Some(vkey) = input.virtual_keycode {
+		                            vcode_to_code(vkey)
+	                            } else {
+		                            scan_to_code(input.scancode)
+	                            };
+
+                                let key = vk_to_key(
+                                    input.virtual_keycode.unwrap_or(VirtualKeyCode::NoConvert),
+                                );
+
+                                let hovered = context.hovered_or_root(window_id);
+                                let focused = context.focused.get(&window_id).copied();
+
+                                match s {
+                                    MouseButtonState::Pressed => {
                                         context.event_queue.push_back(
                                             Event::new(WindowEvent::KeyDown(code, key))
-                                                .target(context.hovered)
+                                                .target(focused.unwrap_or(hovered))
                                                 .propagate(Propagation::DownUp),
                                         );
                                     }
-                                }
 
-                                MouseButtonState::Released => {
-                                    if context.focused != Entity::null() {
+                                    MouseButtonState::Released => {
                                         context.event_queue.push_back(
                                             Event::new(WindowEvent::KeyUp(code, key))
-                                                .target(context.focused)
-                                                .propagate(Propagation::DownUp),
-                                        );
-                                    } else {
-                                        context.event_queue.push_back(
-                                            Event::new(WindowEvent::KeyUp(code, key))
-                                                .target(context.hovered)
+                                                .target(focused.unwrap_or(hovered))
                                                 .propagate(Propagation::DownUp),
                                         );
                                     }
@@ -325,11 +646,13 @@ impl Application {
                         }
 
                         glutin::event::WindowEvent::ReceivedCharacter(character) => {
-                            context.event_queue.push_back(
-                                Event::new(WindowEvent::CharInput(character))
-                                    .target(context.focused)
-                                    .propagate(Propagation::Down),
-                            );
+                            if let Some(focused) = context.focused.get(&window_id).copied() {
+                                context.event_queue.push_back(
+                                    Event::new(WindowEvent::CharInput(character))
+                                        .target(focused)
+                                        .propagate(Propagation::Down),
+                                );
+                            }
                         }
 
 
@@ -349,4 +672,4 @@ fn debug(cx: &mut Context, entity: Entity) -> String {
     } else {
         "None".to_string()
     }
-}
\ No newline at end of file
+}